@@ -1,16 +1,69 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::io::BufRead;
 use std::net::TcpListener;
 use std::process::{Child, Command, Stdio};
-use std::sync::Mutex;
-use std::time::{Duration, Instant};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tauri::Emitter;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+enum SidecarStatus {
+    Starting,
+    Healthy,
+    Restarting,
+    Failed,
+}
+
+/// Where the sidecar is reachable. Defaults to TCP; `PATZE_IPC_MODE=native`
+/// opts into a platform-native local channel (Unix socket / named pipe).
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum ApiEndpoint {
+    Tcp { port: u16 },
+    #[cfg(unix)]
+    UnixSocket { path: std::path::PathBuf },
+    #[cfg(windows)]
+    NamedPipe { name: String },
+}
+
+impl ApiEndpoint {
+    fn tcp_port(&self) -> Option<u16> {
+        match self {
+            ApiEndpoint::Tcp { port } => Some(*port),
+            #[cfg(unix)]
+            ApiEndpoint::UnixSocket { .. } => None,
+            #[cfg(windows)]
+            ApiEndpoint::NamedPipe { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiEndpoint::Tcp { port } => write!(f, "tcp://127.0.0.1:{port}"),
+            #[cfg(unix)]
+            ApiEndpoint::UnixSocket { path } => write!(f, "unix:{}", path.display()),
+            #[cfg(windows)]
+            ApiEndpoint::NamedPipe { name } => write!(f, "pipe:{name}"),
+        }
+    }
+}
 
 struct SidecarState {
-    child: Option<Child>,
-    port: u16,
+    child: Option<SidecarChild>,
+    endpoint: ApiEndpoint,
+    status: SidecarStatus,
+    /// Set once on window teardown so the supervisor stops watching for good;
+    /// `stop_api_server` clearing `child` does not set this, so a later
+    /// `restart_api_server` is still supervised.
+    shutdown: bool,
 }
 
-struct ApiServer(Mutex<SidecarState>);
+struct ApiServer(Arc<Mutex<SidecarState>>);
 
 fn find_free_port() -> u16 {
     TcpListener::bind("127.0.0.1:0")
@@ -30,6 +83,36 @@ fn pick_port() -> u16 {
     find_free_port()
 }
 
+/// Picks the transport for a fresh sidecar instance. Defaults to TCP so
+/// `get_api_port` keeps returning a real port for existing callers; set
+/// `PATZE_IPC_MODE=native` to opt into the platform-native local channel
+/// (Unix socket / named pipe) instead.
+fn pick_endpoint() -> ApiEndpoint {
+    let native_requested = std::env::var("PATZE_IPC_MODE").as_deref() == Ok("native");
+    if !native_requested {
+        return ApiEndpoint::Tcp { port: pick_port() };
+    }
+
+    #[cfg(unix)]
+    {
+        let runtime_dir =
+            std::env::var("XDG_RUNTIME_DIR").unwrap_or_else(|_| "/tmp".to_string());
+        let path = std::path::PathBuf::from(runtime_dir)
+            .join(format!("patze-{}.sock", uuid::Uuid::new_v4()));
+        return ApiEndpoint::UnixSocket { path };
+    }
+
+    #[cfg(windows)]
+    {
+        return ApiEndpoint::NamedPipe {
+            name: format!(r"\\.\pipe\patze-{}", uuid::Uuid::new_v4()),
+        };
+    }
+
+    #[allow(unreachable_code)]
+    ApiEndpoint::Tcp { port: pick_port() }
+}
+
 fn resolve_sidecar_path() -> Option<std::path::PathBuf> {
     let exe = std::env::current_exe().ok()?;
     let dir = exe.parent()?;
@@ -65,82 +148,583 @@ fn resolve_dev_sidecar() -> Option<(String, Vec<String>)> {
     None
 }
 
-fn spawn_api_server(port: u16) -> Option<Child> {
+fn apply_endpoint_env(cmd: &mut Command, endpoint: &ApiEndpoint) {
+    match endpoint {
+        ApiEndpoint::Tcp { port } => {
+            cmd.env("PORT", port.to_string()).env("HOST", "127.0.0.1");
+        }
+        #[cfg(unix)]
+        ApiEndpoint::UnixSocket { path } => {
+            cmd.env("PATZE_IPC", path);
+        }
+        #[cfg(windows)]
+        ApiEndpoint::NamedPipe { name } => {
+            cmd.env("PATZE_IPC", name);
+        }
+    }
+}
+
+/// Detaches the child into its own process group (Unix) so the whole tree
+/// it spawns (e.g. `tsx` forking a Node child) can be torn down together.
+#[cfg(unix)]
+fn isolate_process_tree(cmd: &mut Command) {
+    use std::os::unix::process::CommandExt;
+    cmd.process_group(0);
+}
+
+/// A Win32 Job Object handle with `JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE` set,
+/// so closing it tears down every process the sidecar spawned, not just
+/// the direct child.
+#[cfg(windows)]
+struct JobHandle(windows_sys::Win32::Foundation::HANDLE);
+
+#[cfg(windows)]
+unsafe impl Send for JobHandle {}
+
+#[cfg(windows)]
+impl Drop for JobHandle {
+    fn drop(&mut self) {
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.0);
+        }
+    }
+}
+
+#[cfg(windows)]
+fn create_kill_on_close_job() -> Option<JobHandle> {
+    use windows_sys::Win32::Foundation::HANDLE;
+    use windows_sys::Win32::System::JobObjects::{
+        JobObjectExtendedLimitInformation, SetInformationJobObject,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    unsafe {
+        let job: HANDLE = windows_sys::Win32::System::JobObjects::CreateJobObjectW(
+            std::ptr::null(),
+            std::ptr::null(),
+        );
+        if job.is_null() {
+            return None;
+        }
+
+        let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+        info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+
+        let ok = SetInformationJobObject(
+            job,
+            JobObjectExtendedLimitInformation,
+            &info as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        );
+        if ok == 0 {
+            windows_sys::Win32::Foundation::CloseHandle(job);
+            return None;
+        }
+
+        Some(JobHandle(job))
+    }
+}
+
+#[cfg(windows)]
+fn assign_to_job(job: &JobHandle, child: &Child) -> bool {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::System::JobObjects::AssignProcessToJobObject;
+
+    unsafe { AssignProcessToJobObject(job.0, child.as_raw_handle() as _) != 0 }
+}
+
+/// A running sidecar plus whatever OS handle is needed to reliably kill the
+/// whole process tree it may have spawned (e.g. a forked Node process).
+struct SidecarChild {
+    child: Child,
+    #[cfg(windows)]
+    job: Option<JobHandle>,
+}
+
+impl SidecarChild {
+    fn try_wait(&mut self) -> std::io::Result<Option<std::process::ExitStatus>> {
+        self.child.try_wait()
+    }
+}
+
+fn spawn_api_server(endpoint: &ApiEndpoint) -> Option<SidecarChild> {
+    let spawn = |mut cmd: Command| -> Option<SidecarChild> {
+        #[cfg(unix)]
+        isolate_process_tree(&mut cmd);
+
+        let child = cmd.stdout(Stdio::null()).stderr(Stdio::piped()).spawn().ok()?;
+
+        #[cfg(windows)]
+        {
+            let job = create_kill_on_close_job();
+            if let Some(ref job) = job {
+                if !assign_to_job(job, &child) {
+                    eprintln!("[patze] Failed to assign sidecar to job object");
+                }
+            }
+            return Some(SidecarChild { child, job });
+        }
+
+        #[cfg(not(windows))]
+        Some(SidecarChild { child })
+    };
+
     if let Some(bin) = resolve_sidecar_path() {
-        eprintln!("[patze] Starting sidecar: {} (port {port})", bin.display());
-        return Command::new(bin)
-            .env("PORT", port.to_string())
-            .env("HOST", "127.0.0.1")
-            .stdout(Stdio::null())
-            .stderr(Stdio::piped())
-            .spawn()
-            .ok();
-    }
-
-    if let Some((cmd, args)) = resolve_dev_sidecar() {
-        eprintln!("[patze] Starting dev server: {cmd} {} (port {port})", args.join(" "));
-        return Command::new(cmd)
-            .args(&args)
-            .env("PORT", port.to_string())
-            .env("HOST", "127.0.0.1")
-            .stdout(Stdio::null())
-            .stderr(Stdio::piped())
-            .spawn()
-            .ok();
+        eprintln!("[patze] Starting sidecar: {} ({endpoint})", bin.display());
+        let mut cmd = Command::new(bin);
+        apply_endpoint_env(&mut cmd, endpoint);
+        return spawn(cmd);
+    }
+
+    if let Some((cmd_name, args)) = resolve_dev_sidecar() {
+        eprintln!(
+            "[patze] Starting dev server: {cmd_name} {} ({endpoint})",
+            args.join(" ")
+        );
+        let mut cmd = Command::new(cmd_name);
+        cmd.args(&args);
+        apply_endpoint_env(&mut cmd, endpoint);
+        return spawn(cmd);
     }
 
     eprintln!("[patze] No API server binary or dev script found");
     None
 }
 
-fn wait_for_healthy(port: u16, timeout: Duration) -> bool {
-    let start = Instant::now();
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const HEALTH_REQUEST: &[u8] = b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+const DEFAULT_HEALTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Overall time budget for a single readiness wait, shared by every
+/// `wait_for_healthy` call site. Overridable via `PATZE_HEALTH_TIMEOUT_MS`
+/// for slower dev/CI environments instead of being hardcoded per caller.
+fn health_timeout() -> Duration {
+    std::env::var("PATZE_HEALTH_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_HEALTH_TIMEOUT)
+}
+
+/// How long to sleep between `/health` poll attempts. Overridable via
+/// `PATZE_HEALTH_POLL_MS`, for the same reasons as `health_timeout`.
+fn health_poll_interval() -> Duration {
+    std::env::var("PATZE_HEALTH_POLL_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(HEALTH_POLL_INTERVAL)
+}
+
+fn tcp_health_check(port: u16, attempt_timeout: Duration) -> bool {
     let url = format!("http://127.0.0.1:{port}/health");
+    let agent = ureq::AgentBuilder::new().timeout(attempt_timeout).build();
+    agent
+        .get(&url)
+        .call()
+        .map(|response| (200..300).contains(&response.status()))
+        .unwrap_or(false)
+}
+
+/// Raw HTTP/1.1 GET over a Unix domain socket — there's no local crate that
+/// speaks HTTP-over-UDS, so we write the request line by hand and sniff the
+/// status line of the response.
+#[cfg(unix)]
+fn unix_health_check(path: &std::path::Path, attempt_timeout: Duration) -> bool {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let Ok(mut stream) = UnixStream::connect(path) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(attempt_timeout));
+    let _ = stream.set_write_timeout(Some(attempt_timeout));
+    if stream.write_all(HEALTH_REQUEST).is_err() {
+        return false;
+    }
+
+    let mut buf = [0u8; 32];
+    let Ok(n) = stream.read(&mut buf) else {
+        return false;
+    };
+    let status_line = String::from_utf8_lossy(&buf[..n]);
+    status_line.starts_with("HTTP/1.1 2") || status_line.starts_with("HTTP/1.0 2")
+}
+
+#[cfg(windows)]
+fn named_pipe_health_check(name: &str, attempt_timeout: Duration) -> bool {
+    use std::io::{Read, Write};
+
+    let Ok(mut pipe) = std::fs::OpenOptions::new().read(true).write(true).open(name) else {
+        return false;
+    };
+    if pipe.write_all(HEALTH_REQUEST).is_err() {
+        return false;
+    }
+
+    let mut buf = [0u8; 32];
+    let Ok(n) = pipe.read(&mut buf) else {
+        return false;
+    };
+    let _ = attempt_timeout; // named pipe client handles don't expose a per-call timeout here
+    let status_line = String::from_utf8_lossy(&buf[..n]);
+    status_line.starts_with("HTTP/1.1 2") || status_line.starts_with("HTTP/1.0 2")
+}
+
+fn wait_for_healthy(endpoint: &ApiEndpoint, timeout: Duration) -> bool {
+    wait_for_healthy_with(endpoint, timeout, HEALTH_CHECK_TIMEOUT, health_poll_interval())
+}
+
+/// Polls `GET /health` in-process until it answers with a 2xx or `timeout` elapses,
+/// sleeping `poll_interval` between attempts (each attempt capped at `attempt_timeout`).
+fn wait_for_healthy_with(
+    endpoint: &ApiEndpoint,
+    timeout: Duration,
+    attempt_timeout: Duration,
+    poll_interval: Duration,
+) -> bool {
+    let start = Instant::now();
 
     while start.elapsed() < timeout {
-        if let Ok(output) = Command::new("curl")
-            .args(["-sf", "--max-time", "1", &url])
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-        {
-            if output.success() {
-                return true;
-            }
+        let healthy = match endpoint {
+            ApiEndpoint::Tcp { port } => tcp_health_check(*port, attempt_timeout),
+            #[cfg(unix)]
+            ApiEndpoint::UnixSocket { path } => unix_health_check(path, attempt_timeout),
+            #[cfg(windows)]
+            ApiEndpoint::NamedPipe { name } => named_pipe_health_check(name, attempt_timeout),
+        };
+        if healthy {
+            return true;
         }
-        std::thread::sleep(Duration::from_millis(200));
+        std::thread::sleep(poll_interval);
     }
     false
 }
 
-fn kill_child(child: &mut Child) {
-    let pid = child.id();
+const MIN_API_VERSION: &str = "1.0.0";
+const MAX_API_VERSION: &str = "1.999.999";
+const API_VERSION_EVENT: &str = "patze://api-version-mismatch";
+
+#[derive(Clone, serde::Serialize)]
+struct ApiVersionError {
+    message: String,
+}
+
+fn fetch_version_tcp(port: u16, attempt_timeout: Duration) -> Option<String> {
+    let url = format!("http://127.0.0.1:{port}/version");
+    let agent = ureq::AgentBuilder::new().timeout(attempt_timeout).build();
+    let body = agent.get(&url).call().ok()?.into_string().ok()?;
+    extract_version_field(&body)
+}
+
+#[cfg(unix)]
+fn unix_fetch_version(path: &std::path::Path, attempt_timeout: Duration) -> Option<String> {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(path).ok()?;
+    let _ = stream.set_read_timeout(Some(attempt_timeout));
+    let _ = stream.set_write_timeout(Some(attempt_timeout));
+    stream.write_all(b"GET /version HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n").ok()?;
+
+    let mut body = String::new();
+    stream.read_to_string(&mut body).ok()?;
+    extract_version_field(&body)
+}
+
+#[cfg(windows)]
+fn named_pipe_fetch_version(name: &str, attempt_timeout: Duration) -> Option<String> {
+    use std::io::{Read, Write};
+
+    let mut pipe = std::fs::OpenOptions::new().read(true).write(true).open(name).ok()?;
+    let _ = attempt_timeout; // named pipe client handles don't expose a per-call timeout here
+    pipe.write_all(b"GET /version HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n").ok()?;
+
+    let mut body = String::new();
+    pipe.read_to_string(&mut body).ok()?;
+    extract_version_field(&body)
+}
+
+/// Pulls the `"version"` string field out of a `/version` JSON body without
+/// pulling in a JSON parser for what's otherwise a one-field response.
+fn extract_version_field(body: &str) -> Option<String> {
+    let after_key = body.split("\"version\"").nth(1)?;
+    let after_colon = after_key.split_once(':')?.1;
+    let quoted = after_colon.split_once('"')?.1;
+    let value = quoted.split_once('"')?.0;
+    Some(value.to_string())
+}
+
+#[cfg(test)]
+mod extract_version_field_tests {
+    use super::extract_version_field;
+
+    #[test]
+    fn reads_a_compact_body() {
+        assert_eq!(
+            extract_version_field(r#"{"version":"1.2.3"}"#),
+            Some("1.2.3".to_string())
+        );
+    }
+
+    #[test]
+    fn tolerates_whitespace_around_the_colon_and_key() {
+        assert_eq!(
+            extract_version_field(r#"{ "version"   :   "1.2.3" }"#),
+            Some("1.2.3".to_string())
+        );
+    }
+
+    #[test]
+    fn tolerates_a_trailing_comma_and_sibling_fields() {
+        assert_eq!(
+            extract_version_field(r#"{"version":"1.2.3","commit":"abc123",}"#),
+            Some("1.2.3".to_string())
+        );
+    }
+
+    #[test]
+    fn returns_none_when_the_field_is_missing() {
+        assert_eq!(extract_version_field(r#"{"status":"ok"}"#), None);
+    }
+
+    #[test]
+    fn returns_none_for_a_non_string_value() {
+        assert_eq!(extract_version_field(r#"{"version":123}"#), None);
+    }
+
+    #[test]
+    fn returns_none_for_an_empty_body() {
+        assert_eq!(extract_version_field(""), None);
+    }
+}
+
+fn fetch_version(endpoint: &ApiEndpoint, attempt_timeout: Duration) -> Option<String> {
+    match endpoint {
+        ApiEndpoint::Tcp { port } => fetch_version_tcp(*port, attempt_timeout),
+        #[cfg(unix)]
+        ApiEndpoint::UnixSocket { path } => unix_fetch_version(path, attempt_timeout),
+        #[cfg(windows)]
+        ApiEndpoint::NamedPipe { name } => named_pipe_fetch_version(name, attempt_timeout),
+    }
+}
+
+/// Confirms the sidecar's reported `/version` falls within the range this
+/// build of the desktop shell was compiled against, so a stale bundled or
+/// dev binary doesn't silently serve an incompatible API contract.
+fn verify_api_version(endpoint: &ApiEndpoint) -> Result<(), String> {
+    let raw = fetch_version(endpoint, HEALTH_CHECK_TIMEOUT)
+        .ok_or_else(|| "sidecar did not respond to /version".to_string())?;
+    let version = semver::Version::parse(raw.trim())
+        .map_err(|e| format!("sidecar reported an unparseable version '{raw}': {e}"))?;
+    let min = semver::Version::parse(MIN_API_VERSION).expect("MIN_API_VERSION is valid semver");
+    let max = semver::Version::parse(MAX_API_VERSION).expect("MAX_API_VERSION is valid semver");
+
+    if version < min || version > max {
+        return Err(format!(
+            "sidecar version {version} is outside the supported range {MIN_API_VERSION}..={MAX_API_VERSION}"
+        ));
+    }
+    Ok(())
+}
+
+/// Runs the version gate and, on failure, logs and surfaces it to the
+/// webview via [`API_VERSION_EVENT`]. Returns whether the sidecar is safe to trust.
+fn verify_and_report_version(app: &tauri::AppHandle, endpoint: &ApiEndpoint) -> bool {
+    match verify_api_version(endpoint) {
+        Ok(()) => true,
+        Err(message) => {
+            eprintln!("[patze] {message}");
+            let _ = app.emit(API_VERSION_EVENT, ApiVersionError { message });
+            false
+        }
+    }
+}
+
+const API_LOG_EVENT: &str = "patze://api-log";
+
+#[derive(Clone, serde::Serialize)]
+struct ApiLogLine {
+    level: &'static str,
+    message: String,
+    timestamp_ms: u128,
+}
+
+/// Drains the sidecar's stderr line-by-line on a dedicated thread so the pipe
+/// never fills and backpressures the child, forwarding each line to the webview.
+fn spawn_stderr_reader(app: tauri::AppHandle, child: &mut SidecarChild) {
+    let Some(stderr) = child.child.stderr.take() else {
+        return;
+    };
+
+    std::thread::spawn(move || {
+        for line in std::io::BufReader::new(stderr).lines() {
+            let message = match line {
+                Ok(message) => message,
+                // The non-UTF-8 line was already consumed; skip it and keep draining.
+                Err(e) if e.kind() == std::io::ErrorKind::InvalidData => continue,
+                Err(_) => break,
+            };
+            let timestamp_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+            let _ = app.emit(
+                API_LOG_EVENT,
+                ApiLogLine {
+                    level: "stderr",
+                    message,
+                    timestamp_ms,
+                },
+            );
+        }
+    });
+}
+
+/// Kills the sidecar and anything it forked. On Unix the child was launched
+/// in its own process group, so we signal the negative PGID; on Windows the
+/// child was assigned to a job object with kill-on-close semantics, so
+/// dropping that handle tears down the whole tree.
+fn kill_child(child: &mut SidecarChild) {
+    let pid = child.child.id();
     eprintln!("[patze] Stopping API server (pid: {pid})");
 
     #[cfg(unix)]
     {
-        unsafe { libc::kill(pid as i32, libc::SIGTERM); }
+        unsafe {
+            libc::kill(-(pid as i32), libc::SIGTERM);
+        }
         let deadline = Instant::now() + Duration::from_secs(5);
         loop {
-            match child.try_wait() {
+            match child.child.try_wait() {
                 Ok(Some(_)) => break,
                 Ok(None) if Instant::now() < deadline => {
                     std::thread::sleep(Duration::from_millis(100));
                 }
                 _ => {
-                    let _ = child.kill();
-                    let _ = child.wait();
+                    unsafe {
+                        libc::kill(-(pid as i32), libc::SIGKILL);
+                    }
+                    let _ = child.child.kill();
+                    let _ = child.child.wait();
                     break;
                 }
             }
         }
     }
 
-    #[cfg(not(unix))]
+    #[cfg(windows)]
     {
-        let _ = child.kill();
-        let _ = child.wait();
+        // Drop the job handle first: JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE takes
+        // down the whole tree as soon as the last handle closes.
+        child.job.take();
+        let _ = child.child.kill();
+        let _ = child.child.wait();
     }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = child.child.kill();
+        let _ = child.child.wait();
+    }
+}
+
+const MIN_BACKOFF: Duration = Duration::from_millis(200);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Watches the sidecar child and respawns it on unexpected exit, with capped
+/// exponential backoff that resets once a respawned sidecar passes both the
+/// health check and the version gate.
+fn spawn_supervisor(app: tauri::AppHandle, state: Arc<Mutex<SidecarState>>) {
+    std::thread::spawn(move || {
+        let mut backoff = MIN_BACKOFF;
+
+        loop {
+            std::thread::sleep(Duration::from_millis(250));
+
+            let endpoint = match state.lock() {
+                Ok(mut guard) => {
+                    if guard.shutdown {
+                        return;
+                    }
+                    match guard.child.as_mut() {
+                        // `stop_api_server` cleared the child without shutting down;
+                        // keep polling in case `restart_api_server` brings one back.
+                        None => continue,
+                        Some(child) => match child.try_wait() {
+                            Ok(None) => continue, // still running
+                            Ok(Some(_)) | Err(_) => guard.endpoint.clone(),
+                        },
+                    }
+                }
+                Err(_) => return,
+            };
+
+            eprintln!("[patze] API server exited unexpectedly, restarting in {backoff:?}");
+            if let Ok(mut guard) = state.lock() {
+                guard.status = SidecarStatus::Restarting;
+            }
+            std::thread::sleep(backoff);
+
+            // Re-check before spawning: `WindowEvent::Destroyed` or
+            // `stop_api_server` may have landed while we were backing off.
+            {
+                let Ok(mut guard) = state.lock() else { return };
+                if guard.shutdown {
+                    if let Some(ref mut child) = guard.child {
+                        kill_child(child);
+                    }
+                    guard.child = None;
+                    return;
+                }
+                if guard.child.is_none() {
+                    // `stop_api_server` cleared the child during the backoff window;
+                    // nothing to respawn until a future `restart_api_server` call.
+                    continue;
+                }
+            }
+
+            match spawn_api_server(&endpoint) {
+                Some(mut child) => {
+                    spawn_stderr_reader(app.clone(), &mut child);
+                    let healthy = wait_for_healthy(&endpoint, health_timeout());
+                    let version_ok = healthy && verify_and_report_version(&app, &endpoint);
+
+                    // Re-check again: teardown/stop may have landed while we were
+                    // spawning or health-checking, in which case don't resurrect it.
+                    let Ok(mut guard) = state.lock() else { return };
+                    if guard.shutdown {
+                        kill_child(&mut child);
+                        return;
+                    }
+                    if guard.child.is_none() {
+                        kill_child(&mut child);
+                        continue;
+                    }
+
+                    guard.child = Some(child);
+                    guard.status = if version_ok {
+                        backoff = MIN_BACKOFF;
+                        SidecarStatus::Healthy
+                    } else {
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                        SidecarStatus::Failed
+                    };
+                }
+                None => {
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    if let Ok(mut guard) = state.lock() {
+                        // A cleared child means stop/restart took over meanwhile;
+                        // leave their status alone.
+                        if guard.child.is_some() {
+                            guard.status = SidecarStatus::Failed;
+                        }
+                    }
+                }
+            }
+        }
+    });
 }
 
 fn current_target_triple() -> &'static str {
@@ -159,30 +743,167 @@ fn current_target_triple() -> &'static str {
     }
 }
 
+/// Returns the sidecar's TCP port, or the sentinel `0` (never a valid port)
+/// if the sidecar is on a non-TCP endpoint (`PATZE_IPC_MODE=native`) or the
+/// state lock is poisoned. Callers that need to support Unix sockets /
+/// named pipes should use `get_api_endpoint` instead.
 #[tauri::command]
 fn get_api_port(state: tauri::State<'_, ApiServer>) -> u16 {
-    state.0.lock().map(|s| s.port).unwrap_or(9700)
+    state
+        .0
+        .lock()
+        .ok()
+        .and_then(|s| s.endpoint.tcp_port())
+        .unwrap_or(0)
 }
 
-fn main() {
-    let port = pick_port();
-    let child = spawn_api_server(port);
+#[tauri::command]
+fn get_api_endpoint(state: tauri::State<'_, ApiServer>) -> ApiEndpoint {
+    state
+        .0
+        .lock()
+        .map(|s| s.endpoint.clone())
+        .unwrap_or(ApiEndpoint::Tcp { port: 9700 })
+}
 
-    if child.is_some() {
-        let healthy = wait_for_healthy(port, Duration::from_secs(10));
-        if healthy {
-            eprintln!("[patze] API server ready at http://127.0.0.1:{port}");
-        } else {
-            eprintln!("[patze] API server did not become healthy within 10s");
+#[tauri::command]
+fn get_api_status(state: tauri::State<'_, ApiServer>) -> SidecarStatus {
+    state
+        .0
+        .lock()
+        .map(|s| s.status)
+        .unwrap_or(SidecarStatus::Failed)
+}
+
+#[derive(Clone, serde::Serialize)]
+struct RestartResult {
+    endpoint: ApiEndpoint,
+    healthy: bool,
+}
+
+/// Tears down the current sidecar (if any), spawns a fresh one on a newly
+/// picked endpoint, and waits for it to report healthy before returning.
+#[tauri::command]
+fn restart_api_server(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ApiServer>,
+) -> Result<RestartResult, String> {
+    {
+        let mut guard = state
+            .0
+            .lock()
+            .map_err(|_| "sidecar state poisoned".to_string())?;
+        if let Some(ref mut child) = guard.child {
+            kill_child(child);
         }
+        guard.child = None;
+        guard.status = SidecarStatus::Restarting;
     }
 
-    let state = ApiServer(Mutex::new(SidecarState { child, port }));
+    let endpoint = pick_endpoint();
+    let mut child = spawn_api_server(&endpoint);
+    if let Some(c) = child.as_mut() {
+        spawn_stderr_reader(app.clone(), c);
+    }
+    let healthy = wait_for_healthy(&endpoint, health_timeout());
+    let version_ok = healthy && verify_and_report_version(&app, &endpoint);
+
+    let mut guard = state
+        .0
+        .lock()
+        .map_err(|_| "sidecar state poisoned".to_string())?;
+
+    // Re-check: `stop_api_server` or `WindowEvent::Destroyed` may have landed
+    // while we were spawning or health-checking, in which case don't
+    // resurrect the sidecar after the app already tore it down. `status` is
+    // still `Restarting` (set above) unless one of those raced us, since
+    // we're the ones who cleared `child` to `None` up front.
+    if guard.shutdown {
+        if let Some(mut child) = child {
+            kill_child(&mut child);
+        }
+        return Err("sidecar shut down during restart".to_string());
+    }
+    if guard.status != SidecarStatus::Restarting {
+        if let Some(mut child) = child {
+            kill_child(&mut child);
+        }
+        return Err("sidecar stopped during restart".to_string());
+    }
+
+    guard.child = child;
+    guard.endpoint = endpoint.clone();
+    guard.status = if version_ok {
+        SidecarStatus::Healthy
+    } else {
+        SidecarStatus::Failed
+    };
 
+    Ok(RestartResult {
+        endpoint,
+        healthy: version_ok,
+    })
+}
+
+#[tauri::command]
+fn stop_api_server(state: tauri::State<'_, ApiServer>) -> Result<(), String> {
+    let mut guard = state
+        .0
+        .lock()
+        .map_err(|_| "sidecar state poisoned".to_string())?;
+    if let Some(ref mut child) = guard.child {
+        kill_child(child);
+    }
+    guard.child = None;
+    guard.status = SidecarStatus::Failed;
+    Ok(())
+}
+
+fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .manage(state)
-        .invoke_handler(tauri::generate_handler![get_api_port])
+        .setup(|app| {
+            let endpoint = pick_endpoint();
+            let mut child = spawn_api_server(&endpoint);
+
+            let status = if let Some(c) = child.as_mut() {
+                spawn_stderr_reader(app.handle().clone(), c);
+                let healthy = wait_for_healthy(&endpoint, health_timeout());
+                if healthy {
+                    eprintln!("[patze] API server ready at {endpoint}");
+                    if verify_and_report_version(app.handle(), &endpoint) {
+                        SidecarStatus::Healthy
+                    } else {
+                        SidecarStatus::Failed
+                    }
+                } else {
+                    eprintln!(
+                        "[patze] API server did not become healthy within {:?}",
+                        health_timeout()
+                    );
+                    SidecarStatus::Failed
+                }
+            } else {
+                SidecarStatus::Failed
+            };
+
+            let state = Arc::new(Mutex::new(SidecarState {
+                child,
+                endpoint,
+                status,
+                shutdown: false,
+            }));
+            spawn_supervisor(app.handle().clone(), state.clone());
+            app.manage(ApiServer(state));
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            get_api_port,
+            get_api_endpoint,
+            get_api_status,
+            restart_api_server,
+            stop_api_server
+        ])
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::Destroyed = event {
                 if let Some(api) = window.try_state::<ApiServer>() {
@@ -191,6 +912,7 @@ fn main() {
                             kill_child(child);
                         }
                         guard.child = None;
+                        guard.shutdown = true;
                     }
                 }
             }